@@ -68,37 +68,38 @@
 //! }
 //! ```
 //!
+//! ## Scoped Deletion
+//!
+//! ```rust
+//! use delete::TempDir;
+//!
+//! fn main() {
+//!   let guard = TempDir::new("scratch");
+//!   // ... do work inside the scratch folder ...
+//!   // scratch, and everything in it, is removed here, when `guard` drops.
+//! }
+//! ```
+//!
 //! ### Credits
 //!
 //! [tokio](https://crates.io/crates/tokio)
 
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
 use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::sync::Semaphore;
 
-fn walkdir(path: &str) -> (Vec<String>, Vec<String>) {
-    let mut files_vec: Vec<String> = vec![];
-    let mut folders_vec: Vec<String> = vec![];
-    for entry in jwalk::WalkDir::new(path) {
-        let entry = entry.unwrap();
-        if entry.path().is_file() {
-            files_vec.push(entry.path().to_str().unwrap().to_string());
-        } else {
-            folders_vec.push(entry.path().to_str().unwrap().to_string());
-        }
-    }
-    (files_vec, folders_vec)
-}
+mod prune;
+mod temp_path;
+pub use prune::{prune_empty_parents, prune_empty_parents_async};
+pub use temp_path::{TempDir, TempPath};
 
-async fn priv_delete_files(files: &[String]) {
-    for f in files {
-        tokio::fs::remove_file(f).await.unwrap();
-    }
-}
+type DeleteErrors = Vec<(PathBuf, std::io::Error)>;
 
-async fn priv_delete_folder(folders: &[String]) {
-    for f in folders {
-        let _ = tokio::fs::remove_dir_all(f).await;
-    }
-}
+/// Default bound on the number of delete syscalls `rapid_delete_dir_all` lets run at once, used
+/// when `max_concurrency` isn't given.
+const DEFAULT_MAX_CONCURRENCY: usize = 256;
 
 /// Delete a file from the filesystem.
 ///
@@ -113,7 +114,7 @@ async fn priv_delete_folder(folders: &[String]) {
 /// }
 /// ```
 ///
-pub fn delete_file(path: &str) -> std::io::Result<()> {
+pub fn delete_file(path: impl AsRef<Path>) -> std::io::Result<()> {
     std::fs::remove_file(path)?;
     Ok(())
 }
@@ -132,7 +133,7 @@ pub fn delete_file(path: &str) -> std::io::Result<()> {
 /// }
 /// ```
 ///
-pub async fn delete_file_async(path: &str) -> std::io::Result<()> {
+pub async fn delete_file_async(path: impl AsRef<Path>) -> std::io::Result<()> {
     tokio::fs::remove_file(path).await?;
     Ok(())
 }
@@ -150,7 +151,7 @@ pub async fn delete_file_async(path: &str) -> std::io::Result<()> {
 /// }
 /// ```
 ///
-pub fn delete_folder(path: &str) -> std::io::Result<()> {
+pub fn delete_folder(path: impl AsRef<Path>) -> std::io::Result<()> {
     std::fs::remove_dir(path)?;
     Ok(())
 }
@@ -169,7 +170,7 @@ pub fn delete_folder(path: &str) -> std::io::Result<()> {
 /// }
 /// ```
 ///
-pub async fn delete_folder_async(path: &str) -> std::io::Result<()> {
+pub async fn delete_folder_async(path: impl AsRef<Path>) -> std::io::Result<()> {
     tokio::fs::remove_dir(path).await?;
     Ok(())
 }
@@ -187,7 +188,7 @@ pub async fn delete_folder_async(path: &str) -> std::io::Result<()> {
 /// }
 /// ```
 ///
-pub fn delete_folder_all(path: &str) -> std::io::Result<()> {
+pub fn delete_folder_all(path: impl AsRef<Path>) -> std::io::Result<()> {
     std::fs::remove_dir_all(path)?;
     Ok(())
 }
@@ -206,7 +207,7 @@ pub fn delete_folder_all(path: &str) -> std::io::Result<()> {
 /// }
 /// ```
 ///
-pub async fn delete_folder_all_async(path: &str) -> std::io::Result<()> {
+pub async fn delete_folder_all_async(path: impl AsRef<Path>) -> std::io::Result<()> {
     tokio::fs::remove_dir_all(path).await?;
     Ok(())
 }
@@ -215,17 +216,31 @@ pub async fn delete_folder_all_async(path: &str) -> std::io::Result<()> {
 ///
 /// Benchmarked to be 2-3x faster than `std::fs::remove_dir_all()`
 ///
-/// Uses tokio workers to delete files and folders parallely.
+/// Drives jwalk's parallel walker and dispatches each file removal to a tokio task as soon as
+/// the entry arrives, instead of collecting the whole tree into memory first. Directories are
+/// only ever removed with a plain `remove_dir`, sorted so the deepest ones go first, which means
+/// every directory is already empty by the time it's its turn — no redundant `remove_dir_all`
+/// traversals, and no final cleanup pass on `path` itself (it's just another directory in the
+/// walk). In-flight delete futures are bounded by a `tokio::sync::Semaphore`, sized by
+/// `max_concurrency`, so deleting something like `node_modules` on a slow or networked
+/// filesystem can't spawn an unbounded burst of syscalls.
 ///
-/// ## Parameters
-/// path: `&str` - path to the folder to delete
+/// A partial failure (a permission error, or a file that vanished mid-walk) does not abort the
+/// whole operation or panic. Every task collects the `(path, error)` pairs it hits and keeps
+/// going, so deletion of unaffected files and folders still completes. If nothing went wrong,
+/// `Ok(())` is returned; otherwise `Err` carries every failure that was observed.
 ///
-/// (Optional) folders_chunk_size: `Option<u64>` - number of folders to be deleted per worker.
+/// ## Parameters
+/// path: `impl AsRef<Path>` - path to the folder to delete
 ///
-/// if this value is lower, more workers are spawned.
-/// (Optional) files_chunk_size: `Option<u64>` - number of files to be deleted per worker.
+/// (Optional) follow_symlinks: `Option<bool>` - when `false` (the default), symlinks are never
+/// followed while walking, so a symlink's `file_type()` reflects the link itself rather than its
+/// target, and it's deleted with `remove_file` regardless of what it points at. A recursive
+/// delete therefore can't escape the target tree through a symlinked directory. Pass `Some(true)`
+/// to resolve symlinks like the rest of `std::fs` does.
 ///
-/// if this value is lower, more workers are spawned.
+/// (Optional) max_concurrency: `Option<usize>` - the maximum number of delete syscalls allowed
+/// to be in flight at once. Defaults to 256; values below 1 are treated as 1.
 ///
 /// Uses `tokio::fs` internally.
 /// ## Examples
@@ -240,53 +255,172 @@ pub async fn delete_folder_all_async(path: &str) -> std::io::Result<()> {
 /// ```
 ///
 pub async fn rapid_delete_dir_all(
-    path: &str,
-    folders_chunk_size: Option<u64>,
-    files_chunk_size: Option<u64>,
-) -> std::io::Result<()> {
-    let (files, directories) = walkdir(path);
+    path: impl AsRef<Path>,
+    follow_symlinks: Option<bool>,
+    max_concurrency: Option<usize>,
+) -> Result<(), DeleteErrors> {
+    let path = path.as_ref();
+    let follow_symlinks = follow_symlinks.unwrap_or(false);
+    // A semaphore with 0 permits would never let an acquire through, hanging the whole delete;
+    // clamp to at least 1 so `max_concurrency` can only ever serialize, never deadlock.
+    let semaphore = Arc::new(Semaphore::new(
+        max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY).max(1),
+    ));
 
-    let mut workers = FuturesUnordered::new();
+    let mut errors: DeleteErrors = vec![];
+    // (path, depth) - `entry.depth()` is free (no extra stat), so we keep it alongside the path
+    // instead of re-deriving depth from the path's component count later.
+    let mut directories: Vec<(PathBuf, usize)> = vec![];
+    let mut file_workers = FuturesUnordered::new();
 
-    let file_chunk_size;
+    let walker = jwalk::WalkDir::new(path).follow_links(follow_symlinks);
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let path = e.path().map(|p| p.to_path_buf()).unwrap_or_default();
+                errors.push((path, e.into()));
+                continue;
+            }
+        };
 
-    if files_chunk_size.is_some() {
-        file_chunk_size = files_chunk_size.unwrap();
-    } else {
-        file_chunk_size = 350;
-    }
+        // `file_type()` is populated during the walk (no extra syscall) and already reflects
+        // symlink-vs-target correctly for whatever `follow_links` was configured above.
+        if entry.file_type().is_dir() {
+            directories.push((entry.path(), entry.depth()));
+            continue;
+        }
 
-    let chunks = files.chunks(file_chunk_size as usize);
+        let entry_path = entry.path();
 
-    for chunk in chunks {
-        workers.push(async move {
-            priv_delete_files(chunk).await;
+        // Acquiring the permit here, before spawning, bounds not just in-flight syscalls but
+        // how many tokio tasks (and their path buffers) can pile up while the walk runs ahead.
+        let permit = semaphore.clone().acquire_owned().await.expect("semaphore closed");
+        let join_path = entry_path.clone();
+        let handle = tokio::spawn(async move {
+            let _permit = permit;
+            tokio::fs::remove_file(&entry_path)
+                .await
+                .err()
+                .map(|e| (entry_path, e))
+        });
+        file_workers.push(async move {
+            match handle.await {
+                Ok(result) => result,
+                Err(join_err) => Some((join_path, std::io::Error::other(join_err))),
+            }
         });
     }
 
-    while workers.next().await.is_some() {}
+    while let Some(result) = file_workers.next().await {
+        if let Some(error) = result {
+            errors.push(error);
+        }
+    }
+
+    // Deepest directories first, so by the time a directory's turn comes every entry it
+    // contained (files and subdirectories alike) is already gone and `remove_dir` just works.
+    directories.sort_by_key(|(_, depth)| std::cmp::Reverse(*depth));
+
+    let mut depth_group_start = 0;
+    while depth_group_start < directories.len() {
+        let depth = directories[depth_group_start].1;
+        let mut depth_group_end = depth_group_start;
+        while depth_group_end < directories.len() && directories[depth_group_end].1 == depth {
+            depth_group_end += 1;
+        }
 
-    let folder_chunk_size;
+        let mut dir_workers = FuturesUnordered::new();
+        for (dir, _) in &directories[depth_group_start..depth_group_end] {
+            let permit = semaphore.clone().acquire_owned().await.expect("semaphore closed");
+            let dir = dir.clone();
+            let join_path = dir.clone();
+            let handle = tokio::spawn(async move {
+                let _permit = permit;
+                tokio::fs::remove_dir(&dir).await.err().map(|e| (dir, e))
+            });
+            dir_workers.push(async move {
+                match handle.await {
+                    Ok(result) => result,
+                    Err(join_err) => Some((join_path, std::io::Error::other(join_err))),
+                }
+            });
+        }
 
-    if folders_chunk_size.is_some() {
-        folder_chunk_size = folders_chunk_size.unwrap();
+        while let Some(result) = dir_workers.next().await {
+            if let Some(error) = result {
+                errors.push(error);
+            }
+        }
+
+        depth_group_start = depth_group_end;
+    }
+
+    if errors.is_empty() {
+        Ok(())
     } else {
-        folder_chunk_size = 25;
+        Err(errors)
     }
+}
 
-    let folders = directories.chunks(folder_chunk_size as usize);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut workers = FuturesUnordered::new();
+    fn scratch_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("delete-lib-test-{}-{}", std::process::id(), name))
+    }
 
-    for folder in folders {
-        workers.push(async move {
-            priv_delete_folder(folder).await;
-        })
+    #[tokio::test]
+    async fn rapid_delete_dir_all_aggregates_errors_instead_of_panicking() {
+        let missing = scratch_dir("missing");
+        assert!(!missing.exists());
+
+        let result = rapid_delete_dir_all(&missing, None, None).await;
+
+        let errors = result.expect_err("deleting a path that never existed should error, not panic");
+        assert!(
+            errors.iter().any(|(path, _)| path == &missing),
+            "the aggregated errors should mention the path that failed: {errors:?}"
+        );
     }
 
-    while workers.next().await.is_some() {}
+    #[tokio::test]
+    async fn rapid_delete_dir_all_does_not_follow_symlinks_by_default() {
+        let outside = scratch_dir("symlink-escape-outside");
+        let target = scratch_dir("symlink-escape-target");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        let survivor = outside.join("survivor.txt");
+        std::fs::write(&survivor, b"should not be deleted").unwrap();
+        std::os::unix::fs::symlink(&outside, target.join("escape")).unwrap();
 
-    let _ = std::fs::remove_dir_all(path);
+        rapid_delete_dir_all(&target, None, None).await.unwrap();
 
-    Ok(())
+        assert!(!target.exists(), "the target tree itself should be gone");
+        assert!(
+            survivor.exists(),
+            "a symlink out of the target tree must never cause its target's contents to be removed"
+        );
+
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rapid_delete_dir_all_removes_nested_tree_with_bounded_concurrency() {
+        let root = scratch_dir("depth-ordered");
+        let deep = root.join("a").join("b").join("c");
+        std::fs::create_dir_all(&deep).unwrap();
+        for n in 0..5 {
+            std::fs::write(deep.join(format!("file{n}.txt")), b"scratch").unwrap();
+        }
+        std::fs::write(root.join("a").join("sibling.txt"), b"scratch").unwrap();
+
+        // A max_concurrency of 1 forces every file and directory removal to serialize through
+        // the semaphore, which would deadlock if permits were ever exhausted without being
+        // returned, and would leave directories non-empty if they weren't removed deepest-first.
+        rapid_delete_dir_all(&root, None, Some(1)).await.unwrap();
+
+        assert!(!root.exists());
+    }
 }
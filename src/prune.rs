@@ -0,0 +1,137 @@
+//! Walking up from a deleted path and removing the now-empty ancestor directories it left behind.
+
+use std::path::Path;
+
+/// Walk up from `path`, removing each parent directory as long as it's empty, stopping at the
+/// first non-empty directory, the first removal error, or `stop_at` (if given), so the climb
+/// can't escape above a root you specify.
+///
+/// Pairs naturally with [`delete_folder_all`](crate::delete_folder_all) for leaving no empty
+/// scaffolding behind after a deep recursive delete.
+///
+/// ## Examples
+/// ```
+/// use delete::{delete_folder_all, prune_empty_parents};
+///
+/// fn main() {
+///   delete_folder_all("project/build/out").unwrap();
+///   // Removes "project/build" too, if it's now empty, but never climbs above "project".
+///   prune_empty_parents("project/build/out", Some("project"));
+/// }
+/// ```
+pub fn prune_empty_parents<P: AsRef<Path>>(path: impl AsRef<Path>, stop_at: Option<P>) {
+    let mut current = path.as_ref().to_path_buf();
+    let stop_at = stop_at.as_ref().map(P::as_ref);
+    // Canonicalize once up front so a relative `stop_at` still matches an absolute `path` (and
+    // vice versa). If it can't be resolved (e.g. it doesn't exist), fall back to a raw comparison
+    // rather than silently never stopping.
+    let stop_at_canonical = stop_at.and_then(|p| std::fs::canonicalize(p).ok());
+
+    while current.pop() {
+        let reached_stop_at = match (&stop_at_canonical, std::fs::canonicalize(&current)) {
+            (Some(stop), Ok(current_canonical)) => current_canonical == *stop,
+            _ => Some(current.as_path()) == stop_at,
+        };
+        if reached_stop_at {
+            break;
+        }
+        if std::fs::remove_dir(&current).is_err() {
+            break;
+        }
+    }
+}
+
+/// Async version of [`prune_empty_parents`], using `tokio::fs` internally.
+///
+/// ## Examples
+/// ```
+/// use delete::{delete_folder_all_async, prune_empty_parents_async};
+///
+/// #[tokio::main]
+/// async fn main() {
+///   delete_folder_all_async("project/build/out").await.unwrap();
+///   prune_empty_parents_async("project/build/out", Some("project")).await;
+/// }
+/// ```
+pub async fn prune_empty_parents_async<P: AsRef<Path>>(path: impl AsRef<Path>, stop_at: Option<P>) {
+    let mut current = path.as_ref().to_path_buf();
+    let stop_at = stop_at.as_ref().map(P::as_ref);
+    let stop_at_canonical = match stop_at {
+        Some(p) => tokio::fs::canonicalize(p).await.ok(),
+        None => None,
+    };
+
+    while current.pop() {
+        let reached_stop_at = match (&stop_at_canonical, tokio::fs::canonicalize(&current).await) {
+            (Some(stop), Ok(current_canonical)) => current_canonical == *stop,
+            _ => Some(current.as_path()) == stop_at,
+        };
+        if reached_stop_at {
+            break;
+        }
+        if tokio::fs::remove_dir(&current).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_at_boundary_and_leaves_it_in_place() {
+        let root = std::env::temp_dir().join(format!("delete-prune-test-{}", std::process::id()));
+        let nested = root.join("a").join("b").join("c");
+        std::fs::create_dir_all(&nested).unwrap();
+        // prune_empty_parents assumes the caller already deleted `path` itself (as
+        // delete_folder_all does in the doc example) and only climbs from its parent.
+        std::fs::remove_dir(&nested).unwrap();
+
+        prune_empty_parents(&nested, Some(&root));
+
+        assert!(root.exists(), "stop_at boundary should never be removed");
+        assert!(!root.join("a").exists(), "everything below stop_at should be pruned");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn stops_at_boundary_given_as_non_normalized_path() {
+        let root = std::env::temp_dir().join(format!("delete-prune-test-rel-{}", std::process::id()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::remove_dir(&nested).unwrap();
+
+        // Not written the way `root` is, but canonicalizes to the same directory — exercises the
+        // canonicalized comparison without touching the process-wide current directory (tests
+        // run concurrently in the same process, so mutating CWD would be a flakiness hazard).
+        let non_normalized_stop_at = root.join("a").join("..");
+
+        prune_empty_parents(&nested, Some(&non_normalized_stop_at));
+
+        assert!(
+            root.exists(),
+            "canonicalized comparison should match a stop_at with non-normalized components"
+        );
+        assert!(!root.join("a").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn stops_on_first_non_empty_directory() {
+        let root = std::env::temp_dir().join(format!("delete-prune-test-nonempty-{}", std::process::id()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join("a").join("keep.txt"), b"keep").unwrap();
+        std::fs::remove_dir(&nested).unwrap();
+
+        prune_empty_parents(&nested, Option::<&Path>::None);
+
+        assert!(root.join("a").exists(), "non-empty directory must survive");
+        assert!(!nested.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+//! Scoped deletion guards that remove a file or directory tree when they go out of scope.
+
+use std::mem;
+use std::path::{Path, PathBuf};
+
+/// Deletes a file from the filesystem when dropped.
+///
+/// ## Examples
+/// ```
+/// use delete::TempPath;
+///
+/// fn main() {
+///   let guard = TempPath::new("scratch.txt");
+///   // ... do work with scratch.txt ...
+///   // scratch.txt is removed here, when `guard` drops.
+/// }
+/// ```
+pub struct TempPath {
+    path: PathBuf,
+    armed: bool,
+}
+
+impl TempPath {
+    /// Wrap `path`, arming it for deletion on drop.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            armed: true,
+        }
+    }
+
+    /// The path this guard will delete.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Cancel the scheduled deletion and hand back the path.
+    pub fn leak(mut self) -> PathBuf {
+        self.armed = false;
+        mem::take(&mut self.path)
+    }
+
+    /// Delete the file now, awaiting the result instead of relying on `Drop`'s best-effort
+    /// synchronous cleanup.
+    pub async fn close(mut self) -> std::io::Result<()> {
+        self.armed = false;
+        tokio::fs::remove_file(&self.path).await
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Deletes a directory tree from the filesystem when dropped.
+///
+/// ## Examples
+/// ```
+/// use delete::TempDir;
+///
+/// fn main() {
+///   let guard = TempDir::new("scratch");
+///   // ... do work inside the scratch folder ...
+///   // scratch, and everything in it, is removed here, when `guard` drops.
+/// }
+/// ```
+pub struct TempDir {
+    path: PathBuf,
+    armed: bool,
+}
+
+impl TempDir {
+    /// Wrap `path`, arming it for recursive deletion on drop.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            armed: true,
+        }
+    }
+
+    /// The path this guard will delete.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Cancel the scheduled deletion and hand back the path.
+    pub fn leak(mut self) -> PathBuf {
+        self.armed = false;
+        mem::take(&mut self.path)
+    }
+
+    /// Delete the directory tree now, awaiting the result instead of relying on `Drop`'s
+    /// best-effort synchronous cleanup.
+    pub async fn close(mut self) -> std::io::Result<()> {
+        self.armed = false;
+        tokio::fs::remove_dir_all(&self.path).await
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("delete-temp-path-test-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn temp_path_deletes_on_drop() {
+        let path = scratch_path("file-drop");
+        std::fs::write(&path, b"scratch").unwrap();
+
+        {
+            let _guard = TempPath::new(&path);
+            assert!(path.exists());
+        }
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn temp_path_leak_cancels_deletion() {
+        let path = scratch_path("file-leak");
+        std::fs::write(&path, b"scratch").unwrap();
+
+        let guard = TempPath::new(&path);
+        let leaked = guard.leak();
+
+        assert_eq!(leaked, path);
+        assert!(path.exists(), "leak() must cancel the deletion");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn temp_path_close_deletes_and_disarms() {
+        let path = scratch_path("file-close");
+        std::fs::write(&path, b"scratch").unwrap();
+
+        TempPath::new(&path).close().await.unwrap();
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn temp_dir_deletes_tree_on_drop() {
+        let path = scratch_path("dir-drop");
+        std::fs::create_dir_all(path.join("nested")).unwrap();
+        std::fs::write(path.join("nested").join("file.txt"), b"scratch").unwrap();
+
+        {
+            let _guard = TempDir::new(&path);
+            assert!(path.exists());
+        }
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn temp_dir_leak_cancels_deletion() {
+        let path = scratch_path("dir-leak");
+        std::fs::create_dir_all(&path).unwrap();
+
+        let guard = TempDir::new(&path);
+        let leaked = guard.leak();
+
+        assert_eq!(leaked, path);
+        assert!(path.exists(), "leak() must cancel the deletion");
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn temp_dir_close_deletes_tree_and_disarms() {
+        let path = scratch_path("dir-close");
+        std::fs::create_dir_all(path.join("nested")).unwrap();
+
+        TempDir::new(&path).close().await.unwrap();
+
+        assert!(!path.exists());
+    }
+}